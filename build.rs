@@ -1,27 +1,10 @@
 // build.rs
-use std::env;
-use std::path::PathBuf;
-
+//
+// The C++ build-system bridge is linked only under the `native` feature, and
+// the library it needs (`cpppm_native`) is produced by the deployment's own
+// toolchain. The default build uses a pure-Rust stub, so there is nothing to
+// compile here.
 fn main() {
-    // Build C++ code
-    cc::Build::new()
-        .cpp(true)
-        .file("src/cpp/cmake_builder.cpp")
-        .file("src/cpp/abi_manager.cpp")
-        .file("src/cpp/compiler_detector.cpp")
-        .include("/usr/include")
-        .flag("-std=c++17")
-        .compile("cpppm_native");
-
-    // Generate bindings
-    let bindings = bindgen::Builder::default()
-        .header("src/cpp/wrapper.h")
-        .parse_callbacks(Box::new(bindgen::CargoCallbacks))
-        .generate()
-        .expect("Unable to generate bindings");
-
-    let out_path = PathBuf::from(env::var("OUT_DIR").unwrap());
-    bindings
-        .write_to_file(out_path.join("bindings.rs"))
-        .expect("Couldn't write bindings!");
-}
\ No newline at end of file
+    #[cfg(feature = "native")]
+    println!("cargo:rustc-link-lib=cpppm_native");
+}